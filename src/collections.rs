@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implements length-prefixed collection encodings.
+//!
+//! See the `codicon` crate for details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use endicon::Endianness;
+//! use codicon::{Decoder, Encoder};
+//!
+//! let mut bytes = Vec::new();
+//! vec![1u16, 2u16, 3u16].encode(&mut bytes, Endianness::Little).unwrap();
+//!
+//! let items = Vec::<u16>::decode(&mut bytes.as_slice(), Endianness::Little).unwrap();
+//! assert_eq!(items, vec![1u16, 2u16, 3u16]);
+//! ```
+
+use std::convert::TryInto;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::mem::MaybeUninit;
+use std::slice;
+
+use codicon::{Decoder, Encoder};
+
+use crate::Endianness;
+
+impl<T> Decoder<Endianness> for Vec<T>
+where
+    T: Decoder<Endianness, Error = Error>,
+{
+    type Error = Error;
+
+    fn decode(mut reader: impl Read, params: Endianness) -> Result<Self> {
+        let len = u64::decode(&mut reader, params)?;
+
+        // `len` comes straight off the wire, so it must not be trusted as
+        // an allocation request: grow incrementally and let a bogus value
+        // fail via the natural `read_exact`/EOF error path instead of an
+        // allocator panic.
+        let mut items = Vec::new();
+
+        for _ in 0..len {
+            items.push(T::decode(&mut reader, params)?);
+        }
+
+        Ok(items)
+    }
+}
+
+impl<T> Encoder<Endianness> for Vec<T>
+where
+    T: Encoder<Endianness, Error = Error>,
+{
+    type Error = Error;
+
+    fn encode(&self, mut writer: impl Write, params: Endianness) -> Result<()> {
+        (self.len() as u64).encode(&mut writer, params)?;
+
+        for item in self {
+            item.encode(&mut writer, params)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> Decoder<Endianness> for [T; N]
+where
+    T: Decoder<Endianness, Error = Error>,
+{
+    type Error = Error;
+
+    fn decode(mut reader: impl Read, params: Endianness) -> Result<Self> {
+        let len = u64::decode(&mut reader, params)?;
+
+        if len as usize != N {
+            return Err(Error::new(ErrorKind::InvalidData, "array length mismatch"));
+        }
+
+        let mut items = Vec::with_capacity(N);
+
+        for _ in 0..N {
+            items.push(T::decode(&mut reader, params)?);
+        }
+
+        items
+            .try_into()
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "array length mismatch"))
+    }
+}
+
+impl<T, const N: usize> Encoder<Endianness> for [T; N]
+where
+    T: Encoder<Endianness, Error = Error>,
+{
+    type Error = Error;
+
+    fn encode(&self, mut writer: impl Write, params: Endianness) -> Result<()> {
+        (N as u64).encode(&mut writer, params)?;
+
+        for item in self {
+            item.encode(&mut writer, params)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes directly into caller-provided, possibly uninitialized storage.
+///
+/// Unlike [`Decoder::decode`], which returns `Self` by value, this fills
+/// an existing `dst` slot-by-slot and hands back the now-initialized
+/// slice. This avoids materializing a large array on the stack (or
+/// double-initializing it) before moving it into a `Box`.
+pub trait DecodeInto<Params>: Sized {
+    /// The error produced on failure.
+    type Error;
+
+    /// Decodes `dst.len()` values into `dst`, returning the initialized slice.
+    fn decode_into(
+        dst: &mut [MaybeUninit<Self>],
+        reader: impl Read,
+        params: Params,
+    ) -> std::result::Result<&mut [Self], Self::Error>;
+}
+
+impl<T> DecodeInto<Endianness> for T
+where
+    T: Decoder<Endianness, Error = Error>,
+{
+    type Error = Error;
+
+    fn decode_into(
+        dst: &mut [MaybeUninit<T>],
+        mut reader: impl Read,
+        params: Endianness,
+    ) -> Result<&mut [T]> {
+        // Drops the slots written so far if we bail out partway through,
+        // and is defused once every slot has been initialized.
+        struct Guard<'a, T> {
+            dst: &'a mut [MaybeUninit<T>],
+            initialized: usize,
+        }
+
+        impl<T> Drop for Guard<'_, T> {
+            fn drop(&mut self) {
+                for slot in &mut self.dst[..self.initialized] {
+                    unsafe { slot.assume_init_drop() };
+                }
+            }
+        }
+
+        let mut guard = Guard { dst, initialized: 0 };
+
+        while guard.initialized < guard.dst.len() {
+            let value = T::decode(&mut reader, params)?;
+            guard.dst[guard.initialized].write(value);
+            guard.initialized += 1;
+        }
+
+        let len = guard.dst.len();
+        let ptr = guard.dst.as_mut_ptr();
+        std::mem::forget(guard);
+
+        // SAFETY: the loop above initialized all `len` slots of `dst`.
+        Ok(unsafe { slice::from_raw_parts_mut(ptr as *mut T, len) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::MaybeUninit;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use codicon::{Decoder, Encoder};
+
+    use super::{DecodeInto, Endianness};
+
+    #[test]
+    fn vec_enc() {
+        let mut bytes = Vec::new();
+        vec![1u16, 2u16, 3u16].encode(&mut bytes, Endianness::Little).unwrap();
+        assert_eq!(bytes, vec![3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0, 3, 0]);
+    }
+
+    #[test]
+    fn vec_dec() {
+        let bytes = [3u8, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0, 3, 0];
+        let items = Vec::<u16>::decode(&mut bytes.as_ref(), Endianness::Little).unwrap();
+        assert_eq!(items, vec![1u16, 2u16, 3u16]);
+    }
+
+    #[test]
+    fn array_enc() {
+        let mut bytes = Vec::new();
+        [1u16, 2u16, 3u16].encode(&mut bytes, Endianness::Little).unwrap();
+        assert_eq!(bytes, vec![3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0, 3, 0]);
+    }
+
+    #[test]
+    fn array_dec() {
+        let bytes = [3u8, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0, 3, 0];
+        let items = <[u16; 3]>::decode(&mut bytes.as_ref(), Endianness::Little).unwrap();
+        assert_eq!(items, [1u16, 2u16, 3u16]);
+    }
+
+    #[test]
+    fn array_dec_length_mismatch() {
+        let bytes = [3u8, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0, 3, 0];
+        let err = <[u16; 2]>::decode(&mut bytes.as_ref(), Endianness::Little).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn decode_into_fills_slice() {
+        let bytes = [1u8, 2u8, 3u8];
+        let mut dst = [MaybeUninit::uninit(); 3];
+
+        let items = u8::decode_into(&mut dst, &mut bytes.as_ref(), Endianness::Native).unwrap();
+        assert_eq!(items, &[1u8, 2u8, 3u8]);
+    }
+
+    // The payload is never read back; this type only exists to observe
+    // drops through `DROPS`.
+    #[derive(Debug)]
+    struct Counted(#[allow(dead_code)] u8);
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl Decoder<Endianness> for Counted {
+        type Error = std::io::Error;
+
+        fn decode(reader: impl std::io::Read, params: Endianness) -> std::io::Result<Self> {
+            Ok(Counted(u8::decode(reader, params)?))
+        }
+    }
+
+    #[test]
+    fn decode_into_drops_initialized_slots_on_error() {
+        DROPS.store(0, Ordering::SeqCst);
+
+        let bytes = [1u8, 2u8];
+        let mut dst = [MaybeUninit::uninit(), MaybeUninit::uninit(), MaybeUninit::uninit()];
+
+        let err = Counted::decode_into(&mut dst, &mut bytes.as_ref(), Endianness::Native).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+    }
+}
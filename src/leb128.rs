@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implements the LEB128 variable-length encoding.
+//!
+//! See the `codicon` crate for details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use endicon::Leb128;
+//! use codicon::Encoder;
+//!
+//! let mut bytes = Vec::new();
+//! 300u32.encode(&mut bytes, Leb128).unwrap();
+//! assert_eq!(bytes, vec![0xac, 0x02]);
+//! ```
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use codicon::*;
+
+/// Encode/decode using LEB128 (Little Endian Base 128).
+///
+/// This produces a compact, endianness-independent variable-length
+/// encoding, as used by DWARF and WebAssembly, rather than the fixed
+/// width encodings provided by `Endianness`. Unsigned integer types use
+/// the unsigned form; signed integer types use the sign-extending form.
+#[derive(Copy, Clone, Debug)]
+pub struct Leb128;
+
+macro_rules! leb_impl {
+    () => ();
+
+    ($t:ident $($rest:tt)*) => (
+        impl Decoder<Leb128> for $t {
+            type Error = Error;
+
+            fn decode(mut reader: impl Read, _: Leb128) -> Result<Self> {
+                let mut value: $t = 0;
+                let mut shift: u32 = 0;
+                let mut byte = [0u8; 1];
+
+                loop {
+                    if shift >= $t::BITS {
+                        return Err(Error::new(ErrorKind::InvalidData, "leb128 overflow"));
+                    }
+
+                    reader.read_exact(&mut byte)?;
+                    let low7 = byte[0] & 0x7f;
+
+                    // Bits of this byte above the remaining bit width would
+                    // be silently shifted out of `$t` below; reject them
+                    // instead of losing them.
+                    let remaining = $t::BITS - shift;
+                    if remaining < 7 && low7 >> remaining != 0 {
+                        return Err(Error::new(ErrorKind::InvalidData, "leb128 overflow"));
+                    }
+
+                    value |= (low7 as $t) << shift;
+                    shift += 7;
+
+                    if byte[0] & 0x80 == 0 {
+                        break;
+                    }
+                }
+
+                Ok(value)
+            }
+        }
+
+        impl Encoder<Leb128> for $t {
+            type Error = Error;
+
+            fn encode(&self, mut writer: impl Write, _: Leb128) -> Result<()> {
+                let mut value = *self;
+
+                loop {
+                    let byte = (value & 0x7f) as u8;
+                    value >>= 7;
+
+                    if value == 0 {
+                        writer.write_all(&[byte])?;
+                        break;
+                    }
+
+                    writer.write_all(&[byte | 0x80])?;
+                }
+
+                Ok(())
+            }
+        }
+
+        leb_impl!(!$t);
+        leb_impl!($($rest)*);
+    );
+
+    (!$t:ident) => (
+        #[cfg(test)]
+        mod $t {
+            use codicon::{Decoder, Encoder};
+            use super::Leb128;
+
+            const V: $t = 128 as $t;
+            const E: [u8; 2] = [0x80, 0x01];
+
+            #[test]
+            fn enc() {
+                let mut bytes = Vec::new();
+                V.encode(&mut bytes, Leb128).unwrap();
+                assert_eq!(bytes, E);
+            }
+
+            #[test]
+            fn dec() {
+                let x = $t::decode(&mut E.as_ref(), Leb128).unwrap();
+                assert_eq!(x, V);
+            }
+
+            #[test]
+            fn dec_overflow() {
+                // The final byte contributes only $t::BITS % 7 remaining
+                // bits (or all 7 if that's 0); a payload with
+                // any higher bit set must not be silently truncated.
+                let n = ($t::BITS as usize + 6) / 7;
+                let mut bytes = vec![0xffu8; n];
+                bytes[n - 1] = 0x7f;
+
+                let err = $t::decode(&mut bytes.as_slice(), Leb128).unwrap_err();
+                assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            }
+        }
+    );
+}
+
+leb_impl! {
+    u8 u16 u32 u64 u128 usize
+}
+
+macro_rules! sleb_impl {
+    () => ();
+
+    ($t:ident $($rest:tt)*) => (
+        impl Decoder<Leb128> for $t {
+            type Error = Error;
+
+            fn decode(mut reader: impl Read, _: Leb128) -> Result<Self> {
+                let mut value: $t = 0;
+                let mut shift: u32 = 0;
+                let mut byte = [0u8; 1];
+
+                loop {
+                    if shift >= $t::BITS {
+                        return Err(Error::new(ErrorKind::InvalidData, "leb128 overflow"));
+                    }
+
+                    reader.read_exact(&mut byte)?;
+                    let low7 = byte[0] & 0x7f;
+
+                    // Bits of this byte above the remaining bit width are
+                    // shifted out of `$t` below. They carry no information
+                    // only if they all equal the sign bit (the top bit that
+                    // does fit); otherwise reject them instead of losing them.
+                    let remaining = $t::BITS - shift;
+                    if remaining < 7 {
+                        let upper = low7 >> (remaining - 1);
+                        if upper != 0 && upper != 0x7f >> (remaining - 1) {
+                            return Err(Error::new(ErrorKind::InvalidData, "leb128 overflow"));
+                        }
+                    }
+
+                    value |= (low7 as $t) << shift;
+                    shift += 7;
+
+                    if byte[0] & 0x80 == 0 {
+                        if shift < $t::BITS && byte[0] & 0x40 != 0 {
+                            value |= !0 << shift;
+                        }
+
+                        break;
+                    }
+                }
+
+                Ok(value)
+            }
+        }
+
+        impl Encoder<Leb128> for $t {
+            type Error = Error;
+
+            fn encode(&self, mut writer: impl Write, _: Leb128) -> Result<()> {
+                let mut value = *self;
+
+                loop {
+                    let byte = (value & 0x7f) as u8;
+                    value >>= 7;
+
+                    if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                        writer.write_all(&[byte])?;
+                        break;
+                    }
+
+                    writer.write_all(&[byte | 0x80])?;
+                }
+
+                Ok(())
+            }
+        }
+
+        sleb_impl!(!$t);
+        sleb_impl!($($rest)*);
+    );
+
+    (!$t:ident) => (
+        #[cfg(test)]
+        mod $t {
+            use codicon::{Decoder, Encoder};
+            use super::Leb128;
+
+            const V: $t = 100 as $t;
+            const E: [u8; 2] = [0xe4, 0x00];
+
+            #[test]
+            fn enc() {
+                let mut bytes = Vec::new();
+                V.encode(&mut bytes, Leb128).unwrap();
+                assert_eq!(bytes, E);
+            }
+
+            #[test]
+            fn dec() {
+                let x = $t::decode(&mut E.as_ref(), Leb128).unwrap();
+                assert_eq!(x, V);
+            }
+
+            #[test]
+            fn dec_overflow() {
+                // Same final-byte truncation hazard as the unsigned form:
+                // the final byte's bits above the type width are not all
+                // copies of the sign bit, so they cannot be represented.
+                let n = ($t::BITS as usize + 6) / 7;
+                let mut bytes = vec![0xffu8; n];
+                bytes[n - 1] = 0x3f;
+
+                let err = $t::decode(&mut bytes.as_slice(), Leb128).unwrap_err();
+                assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            }
+        }
+    );
+}
+
+sleb_impl! {
+    i8 i16 i32 i64 i128 isize
+}
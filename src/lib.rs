@@ -25,6 +25,12 @@ use std::io::{Error, Result};
 
 use codicon::*;
 
+mod collections;
+mod leb128;
+
+pub use collections::DecodeInto;
+pub use leb128::Leb128;
+
 /// Endianness to use during encoding/decoding.
 #[derive(Copy, Clone, Debug)]
 pub enum Endianness {
@@ -43,6 +49,30 @@ trait FloatEndian<T> {
     fn to_be(self) -> T;
 }
 
+/// Encodes a contiguous run of values in a single pass.
+///
+/// This amortizes the I/O cost of encoding an array or slice across one
+/// `write_all()` instead of one per element.
+pub trait SliceEncoder<Params>: Sized {
+    /// The error produced on failure.
+    type Error;
+
+    /// Encodes `items` to `writer` using `params`.
+    fn encode_slice(items: &[Self], writer: impl Write, params: Params) -> std::result::Result<(), Self::Error>;
+}
+
+/// Decodes a contiguous run of values in a single pass.
+///
+/// This amortizes the I/O cost of decoding an array or slice across one
+/// `read_exact()` instead of one per element.
+pub trait SliceDecoder<Params>: Sized {
+    /// The error produced on failure.
+    type Error;
+
+    /// Decodes `buf.len()` values from `reader` using `params`.
+    fn decode_slice(buf: &mut [Self], reader: impl Read, params: Params) -> std::result::Result<(), Self::Error>;
+}
+
 macro_rules! end_impl {
     () => ();
 
@@ -73,6 +103,30 @@ macro_rules! end_impl {
             }
         }
 
+        impl SliceEncoder<Endianness> for $t {
+            type Error = Error;
+
+            fn encode_slice(items: &[$t], writer: impl Write, params: Endianness) -> Result<()> {
+                let bits: Vec<$i> = items.iter().map(|v| v.to_bits()).collect();
+                $i::encode_slice(&bits, writer, params)
+            }
+        }
+
+        impl SliceDecoder<Endianness> for $t {
+            type Error = Error;
+
+            fn decode_slice(buf: &mut [$t], reader: impl Read, params: Endianness) -> Result<()> {
+                let mut bits = vec![0 as $i; buf.len()];
+                $i::decode_slice(&mut bits, reader, params)?;
+
+                for (dst, src) in buf.iter_mut().zip(bits) {
+                    *dst = $t::from_bits(src);
+                }
+
+                Ok(())
+            }
+        }
+
         end_impl!(!$t);
         end_impl!($($rest)*);
     );
@@ -108,6 +162,51 @@ macro_rules! end_impl {
             }
         }
 
+        impl SliceEncoder<Endianness> for $t {
+            type Error = Error;
+
+            fn encode_slice(items: &[$t], mut writer: impl Write, params: Endianness) -> Result<()> {
+                const S: usize = std::mem::size_of::<$t>();
+                let mut buf = vec![0u8; items.len() * S];
+
+                for (chunk, item) in buf.chunks_exact_mut(S).zip(items) {
+                    let bytes = match params {
+                        Endianness::Native => item.to_ne_bytes(),
+                        Endianness::Little => item.to_le_bytes(),
+                        Endianness::Big => item.to_be_bytes(),
+                    };
+
+                    chunk.copy_from_slice(&bytes);
+                }
+
+                writer.write_all(&buf)?;
+                Ok(())
+            }
+        }
+
+        impl SliceDecoder<Endianness> for $t {
+            type Error = Error;
+
+            fn decode_slice(buf: &mut [$t], mut reader: impl Read, params: Endianness) -> Result<()> {
+                const S: usize = std::mem::size_of::<$t>();
+                let mut bytes = vec![0u8; buf.len() * S];
+                reader.read_exact(&mut bytes)?;
+
+                for (chunk, item) in bytes.chunks_exact(S).zip(buf.iter_mut()) {
+                    let mut b = $t::default().to_ne_bytes();
+                    b.copy_from_slice(chunk);
+
+                    *item = match params {
+                        Endianness::Native => $t::from_ne_bytes(b),
+                        Endianness::Little => $t::from_le_bytes(b),
+                        Endianness::Big => $t::from_be_bytes(b),
+                    };
+                }
+
+                Ok(())
+            }
+        }
+
         end_impl!(!$t);
         end_impl!($($rest)*);
     );
@@ -186,6 +285,36 @@ macro_rules! end_impl {
                     assert_eq!(x, V);
                 }
             }
+
+            mod slice {
+                use super::super::{Endianness, SliceDecoder, SliceEncoder};
+
+                const V: [$t; 3] = [1 as $t, 2 as $t, 3 as $t];
+
+                #[test]
+                fn enc() {
+                    let mut expected = Vec::new();
+                    for v in V.iter() {
+                        expected.extend_from_slice(&v.to_le_bytes());
+                    }
+
+                    let mut bytes = Vec::new();
+                    $t::encode_slice(&V, &mut bytes, Endianness::Little).unwrap();
+                    assert_eq!(bytes, expected);
+                }
+
+                #[test]
+                fn dec() {
+                    let mut bytes = Vec::new();
+                    for v in V.iter() {
+                        bytes.extend_from_slice(&v.to_le_bytes());
+                    }
+
+                    let mut buf = [0 as $t; 3];
+                    $t::decode_slice(&mut buf, &mut bytes.as_slice(), Endianness::Little).unwrap();
+                    assert_eq!(buf, V);
+                }
+            }
         }
     );
 }